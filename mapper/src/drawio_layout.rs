@@ -5,6 +5,7 @@ use std::collections::HashMap;
 pub struct NodeLayout<'a> {
     pub positions: HashMap<u32, (i32, i32)>,    // id -> (x, y)
     pub id_to_node: HashMap<u32, &'a TreeNode>, // id -> node
+    pub depths: HashMap<u32, i32>,               // id -> tree depth, for depth-based palettes
     pub next_id: u32,
 }
 
@@ -13,6 +14,7 @@ impl NodeLayout<'_> {
         NodeLayout {
             positions: HashMap::new(),
             id_to_node: HashMap::new(),
+            depths: HashMap::new(),
             next_id: 2,
         }
     }
@@ -24,13 +26,14 @@ pub fn layout_tree<'a>(
     depth: i32,
     y_offset: &mut i32,
     layout: &mut NodeLayout<'a>,
-    parent_id: u32,
+    _parent_id: u32,
     edges: &mut Vec<(u32, u32)>,
 ) -> u32 {
     let local_y = *y_offset;
     let id = layout.next_id;
     layout.next_id += 1;
     layout.id_to_node.insert(id, node);
+    layout.depths.insert(id, depth);
 
     // Assign position: landscape (x = depth, y = sibling order)
     layout.positions.insert(id, (depth * 240, local_y));
@@ -0,0 +1,246 @@
+use crate::TreeNode;
+use std::collections::HashMap;
+use taffy::prelude::*;
+
+/// Stable identifier for a `TreeNode` within a single layout pass.
+///
+/// Derived from the node's address, which is valid for as long as the
+/// `TreeNode` tree being laid out is not reallocated.
+pub type NodeId = usize;
+
+/// Computes the `NodeId` a `TreeNode` will be keyed under in a `position()` result.
+pub fn node_id(node: &TreeNode) -> NodeId {
+    node as *const TreeNode as usize
+}
+
+/// Direction the tree grows in.
+///
+/// No CLI flag sets `Vertical` yet (everything currently runs through
+/// `LayoutConfig::default()`), but it's part of `TreeLayout`'s public
+/// config surface so a caller embedding this as a library can pick it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Orientation {
+    /// Siblings stack top-to-bottom, depth increases left-to-right.
+    Horizontal,
+    /// Siblings stack left-to-right, depth increases top-to-bottom.
+    Vertical,
+}
+
+/// Cross-axis alignment of a node's children within its own extent.
+///
+/// No CLI flag sets this either; every caller runs with the default
+/// `Start`. See [`Orientation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Alignment {
+    Start,
+    Center,
+    End,
+}
+
+/// Shared knobs for any `TreeLayout` implementation.
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutConfig {
+    pub orientation: Orientation,
+    pub alignment: Alignment,
+    pub h_spacing: i32,
+    pub v_spacing: i32,
+    pub char_width: i32,
+    pub padding: i32,
+    pub min_width: i32,
+    pub node_height: i32,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            orientation: Orientation::Horizontal,
+            alignment: Alignment::Start,
+            h_spacing: 40,
+            v_spacing: 20,
+            char_width: 10,
+            padding: 18,
+            min_width: 80,
+            node_height: 32,
+        }
+    }
+}
+
+/// Computes absolute `(x, y, width, height)` rectangles for every node in a tree.
+pub trait TreeLayout {
+    fn position(&self, root: &TreeNode) -> HashMap<NodeId, (i32, i32, i32, i32)>;
+}
+
+/// Flexbox-based layout backend built on the `taffy` engine.
+///
+/// Each directory becomes a flex container (`flex_direction` set from
+/// [`Orientation`]) whose children are its child subtrees; each node's own box
+/// is `name.chars().count() * char_width + 2 * padding` wide (at least
+/// `min_width`) and `node_height` tall.
+/// taffy reports each node's `Layout` relative to its parent, so absolute
+/// coordinates are recovered with a pre-order walk that sums ancestor
+/// `location` values. taffy node ids carry no payload, so a side table maps
+/// them back to the `TreeNode` they were built from.
+pub struct TaffyLayout {
+    pub config: LayoutConfig,
+}
+
+impl TaffyLayout {
+    pub fn new(config: LayoutConfig) -> Self {
+        TaffyLayout { config }
+    }
+
+    /// Siblings stack along the flex direction; depth is expressed as a
+    /// margin on each child equal to its parent's own box plus spacing, so
+    /// the container's natural flow produces the staircase a directory tree
+    /// needs without an extra wrapper node per level.
+    fn flex_direction(&self) -> FlexDirection {
+        match self.config.orientation {
+            Orientation::Horizontal => FlexDirection::Column,
+            Orientation::Vertical => FlexDirection::Row,
+        }
+    }
+
+    fn align_items(&self) -> AlignItems {
+        match self.config.alignment {
+            Alignment::Start => AlignItems::FlexStart,
+            Alignment::Center => AlignItems::Center,
+            Alignment::End => AlignItems::FlexEnd,
+        }
+    }
+
+    /// Mirrors a `TreeNode` subtree into `tree`, recording each taffy node's
+    /// originating `TreeNode` in `side_table`, and returns `(taffy node, own
+    /// width, own height, subtree main-axis extent)` so the caller can indent
+    /// the next depth level and size its own container around this subtree.
+    ///
+    /// The container's main-axis size (height for `Horizontal`, width for
+    /// `Vertical`) is set to the subtree extent rather than the node's own
+    /// size: a directory with three stacked children needs three rows of
+    /// ceiling, not one, or taffy positions the next sibling right on top of
+    /// this node's own descendants.
+    fn build<'a>(
+        &self,
+        tree: &mut TaffyTree<()>,
+        node: &'a TreeNode,
+        side_table: &mut HashMap<taffy::NodeId, &'a TreeNode>,
+    ) -> Result<(taffy::NodeId, f32, f32, f32), taffy::TaffyError> {
+        let name_len = node.name.chars().count() as f32;
+        let own_width = (name_len * self.config.char_width as f32 + 2.0 * self.config.padding as f32)
+            .max(self.config.min_width as f32);
+        let own_height = self.config.node_height as f32;
+        let own_main = match self.config.orientation {
+            Orientation::Horizontal => own_height,
+            Orientation::Vertical => own_width,
+        };
+        let spacing = match self.config.orientation {
+            Orientation::Horizontal => self.config.v_spacing,
+            Orientation::Vertical => self.config.h_spacing,
+        } as f32;
+
+        let mut child_ids = Vec::with_capacity(node.children.len());
+        let mut children_extent = 0.0f32;
+        for (i, child) in node.children.iter().enumerate() {
+            let (child_id, _, _, child_extent) = self.build(tree, child, side_table)?;
+            let mut child_style = tree.style(child_id)?.clone();
+            match self.config.orientation {
+                Orientation::Horizontal => {
+                    child_style.margin.left = length(own_width + self.config.h_spacing as f32);
+                }
+                Orientation::Vertical => {
+                    child_style.margin.top = length(own_height + self.config.v_spacing as f32);
+                }
+            }
+            tree.set_style(child_id, child_style)?;
+            child_ids.push(child_id);
+            if i > 0 {
+                children_extent += spacing;
+            }
+            children_extent += child_extent;
+        }
+        let main_extent = own_main.max(children_extent);
+
+        let size = match self.config.orientation {
+            Orientation::Horizontal => Size {
+                width: length(own_width),
+                height: length(main_extent),
+            },
+            Orientation::Vertical => Size {
+                width: length(main_extent),
+                height: length(own_height),
+            },
+        };
+        let style = Style {
+            display: Display::Flex,
+            flex_direction: self.flex_direction(),
+            align_items: Some(self.align_items()),
+            gap: Size {
+                width: length(self.config.h_spacing as f32),
+                height: length(self.config.v_spacing as f32),
+            },
+            size,
+            ..Default::default()
+        };
+
+        let taffy_id = tree.new_with_children(style, &child_ids)?;
+        side_table.insert(taffy_id, node);
+        Ok((taffy_id, own_width, own_height, main_extent))
+    }
+}
+
+impl TreeLayout for TaffyLayout {
+    fn position(&self, root: &TreeNode) -> HashMap<NodeId, (i32, i32, i32, i32)> {
+        let mut tree: TaffyTree<()> = TaffyTree::new();
+        let mut side_table = HashMap::new();
+        let root_id = match self.build(&mut tree, root, &mut side_table) {
+            Ok((id, _, _, _)) => id,
+            Err(e) => {
+                eprintln!("Warning: taffy layout failed to build node tree: {e}");
+                return HashMap::new();
+            }
+        };
+
+        if let Err(e) = tree.compute_layout(root_id, Size::MAX_CONTENT) {
+            eprintln!("Warning: taffy layout failed to compute layout: {e}");
+            return HashMap::new();
+        }
+
+        let mut positions = HashMap::new();
+        self.walk(&tree, &side_table, root_id, 0, 0, &mut positions);
+        positions
+    }
+}
+
+impl TaffyLayout {
+    /// Pre-order walk that converts taffy's parent-relative `location` into
+    /// absolute coordinates by accumulating the offset of every ancestor.
+    fn walk(
+        &self,
+        tree: &TaffyTree<()>,
+        side_table: &HashMap<taffy::NodeId, &TreeNode>,
+        taffy_id: taffy::NodeId,
+        parent_abs_x: i32,
+        parent_abs_y: i32,
+        out: &mut HashMap<NodeId, (i32, i32, i32, i32)>,
+    ) {
+        let Ok(layout) = tree.layout(taffy_id) else {
+            return;
+        };
+        let abs_x = parent_abs_x + layout.location.x as i32;
+        let abs_y = parent_abs_y + layout.location.y as i32;
+
+        if let Some(node) = side_table.get(&taffy_id) {
+            out.insert(
+                node_id(node),
+                (abs_x, abs_y, layout.size.width as i32, layout.size.height as i32),
+            );
+        }
+
+        if let Ok(children) = tree.children(taffy_id) {
+            for child in children {
+                self.walk(tree, side_table, child, abs_x, abs_y, out);
+            }
+        }
+    }
+}
@@ -1,8 +1,11 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use std::io;
 use std::path::Path;
 
+use layout::TreeLayout;
+
 /// Error type for the mapper application
 #[derive(Debug)]
 pub enum MapperError {
@@ -115,107 +118,172 @@ fn build_tree_inner(path: &Path, is_root: bool) -> Option<TreeNode> {
     }
 }
 
-/// Holds layout information for a node
+/// Holds layout information for a node.
+///
+/// No `height` field: a `TreeLayout` backend's reported height for a node is
+/// its container's main-axis extent (which grows to fit the node's own
+/// descendants, see `TaffyLayout::build`), not the 32px the node's own box
+/// is always drawn at, so carrying it here would invite using it for the
+/// wrong thing.
 struct LayoutNode<'a> {
     node: &'a TreeNode,
+    /// Stable id shared with `drawio_layout`, used to address this node's
+    /// `<g>` (and its connector `<path>`) from `svg_script.js`.
+    id: u32,
     x: i32,
     y: i32,
     width: i32,
-    height: i32,
     children: Vec<LayoutNode<'a>>,
 }
 
-/// Compute width and height for each node and position children to avoid overlap
-fn layout_tree(
-    node: &TreeNode,
-    x: i32,
-    y: i32,
-    h_spacing: i32,
-    v_spacing: i32,
-    char_width: i32,
-    padding: i32,
-    min_width: i32,
-    height: i32,
-) -> LayoutNode<'_> {
-    let text_len = node.name.chars().count() as i32;
-    let width = (text_len * char_width + 2 * padding).max(min_width);
-    let mut curr_y = y;
-    let mut children = Vec::new();
-    let mut subtree_height = 0;
-    for child in &node.children {
-        let child_layout = layout_tree(
-            child,
-            x + width + h_spacing,
-            curr_y,
-            h_spacing,
-            v_spacing,
-            char_width,
-            padding,
-            min_width,
-            height,
-        );
-        curr_y += child_layout.height + v_spacing;
-        subtree_height += child_layout.height + v_spacing;
-        children.push(child_layout);
-    }
-    if subtree_height > 0 {
-        subtree_height -= v_spacing; // Remove extra spacing after last child
-    }
-    let node_height = height.max(subtree_height);
+/// Assigns each node in `tree` the same stable numeric id `drawio_layout`
+/// would, keyed by `layout::NodeId` so `layout_from_positions` can look it
+/// up regardless of which `TreeLayout` backend computed the node's rect.
+fn stable_node_ids(tree: &TreeNode) -> HashMap<layout::NodeId, u32> {
+    let mut id_layout = drawio_layout::NodeLayout::new();
+    let mut y_offset = 0;
+    let mut edges = Vec::new();
+    drawio_layout::layout_tree(tree, 0, &mut y_offset, &mut id_layout, 1, &mut edges);
+    id_layout
+        .id_to_node
+        .iter()
+        .map(|(&id, node)| (layout::node_id(node), id))
+        .collect()
+}
+
+/// Builds a `LayoutNode` tree from the absolute rects a `TreeLayout` backend
+/// computed for `node` and its descendants, tagging each with the stable id
+/// `ids` assigned it.
+fn layout_from_positions<'a>(
+    node: &'a TreeNode,
+    positions: &HashMap<layout::NodeId, (i32, i32, i32, i32)>,
+    ids: &HashMap<layout::NodeId, u32>,
+) -> LayoutNode<'a> {
+    let (x, y, width, _) = positions
+        .get(&layout::node_id(node))
+        .copied()
+        .unwrap_or((0, 0, 80, 32));
+    let id = ids.get(&layout::node_id(node)).copied().unwrap_or(0);
+    let children = node
+        .children
+        .iter()
+        .map(|child| layout_from_positions(child, positions, ids))
+        .collect();
     LayoutNode {
         node,
+        id,
         x,
-        y: if subtree_height > height {
-            y + (subtree_height - height) / 2
-        } else {
-            y
-        },
+        y,
         width,
-        height: node_height,
         children,
     }
 }
 
-/// Recursively render SVG for the layout tree
-fn svg_for_layout(layout: &LayoutNode, svg: &mut String, id_prefix: &str) {
-    let node_id = format!("{}-{}-{}", id_prefix, layout.x, layout.y);
-    let (node_color, glow_color) = match layout.node.node_type {
-        NodeType::Directory => ("#00fff7", "#00fff7"), // Neon cyan
-        NodeType::File => ("#39ff14", "#39ff14"),      // Neon green
-    };
-    svg.push_str(&format!(
-        "<g id='{id}' onmouseover=\"this.querySelector('rect').style.fill='#333'\" onmouseout=\"this.querySelector('rect').style.fill='{color}'\" class='node'><rect x='{x}' y='{y}' width='{w}' height='32' rx='12' fill='{color}' filter='url(#glow)' opacity='0.92'/><text x='{tx}' y='{ty}' font-size='16' fill='#000' filter='url(#textglow)' style='font-family:monospace;letter-spacing:0.5px'>{label}</text>",
-        id = node_id,
-        x = layout.x,
-        y = layout.y,
-        w = layout.width,
-        color = node_color,
-        tx = layout.x + 14,
-        ty = layout.y + 22,
-        label = layout.node.name
-    ));
+/// Recursively render SVG for the layout tree, building each element
+/// through `svg_builder` so names containing `&`, `<`, `>` or `"` can't
+/// produce malformed markup.
+///
+/// Each directory group carries a `data-children` attribute listing its
+/// child group ids and a clickable toggle glyph; `svg_script.js` reads
+/// both to collapse/expand the subtree. Groups and their connector `<path>`s
+/// are addressed by `layout.id`, the stable numeric id shared with
+/// `drawio_layout`, rather than a position-derived id, so the script can
+/// address every node deterministically.
+fn svg_for_layout(layout: &LayoutNode, svg: &mut String, depth: i32, palette: &Palette) {
+    let node_id = format!("node-{}", layout.id);
+    let node_color = palette.color_for(depth, &layout.node.node_type);
+    let is_collapsible = matches!(layout.node.node_type, NodeType::Directory) && !layout.children.is_empty();
+
+    let mut children = vec![
+        svg_builder::Rect {
+            x: layout.x,
+            y: layout.y,
+            width: layout.width,
+            height: 32,
+            rx: 12,
+            attrs: vec![
+                ("fill", node_color.to_string()),
+                ("filter", "url(#glow)".to_string()),
+                ("opacity", "0.92".to_string()),
+            ],
+        }
+        .to_string(),
+        svg_builder::Text {
+            x: layout.x + 14,
+            y: layout.y + 22,
+            content: layout.node.name.clone(),
+            attrs: vec![
+                ("font-size", "16".to_string()),
+                ("fill", "#000".to_string()),
+                ("filter", "url(#textglow)".to_string()),
+                ("style", "font-family:monospace;letter-spacing:0.5px".to_string()),
+            ],
+        }
+        .to_string(),
+    ];
+    if is_collapsible {
+        children.push(
+            svg_builder::Text {
+                x: layout.x + layout.width - 18,
+                y: layout.y + 22,
+                content: "-".to_string(),
+                attrs: vec![
+                    ("font-size", "16".to_string()),
+                    ("fill", "#000".to_string()),
+                    ("class", "toggle".to_string()),
+                    ("style", "font-family:monospace;user-select:none".to_string()),
+                ],
+            }
+            .to_string(),
+        );
+    }
     if let NodeType::File = layout.node.node_type {
-        svg.push_str(&format!("<title>File: {}</title>", layout.node.name));
+        children.push(svg_builder::Title(format!("File: {}", layout.node.name)).to_string());
     }
-    svg.push_str("</g>");
-    // Draw connectors
+
+    let mut attrs = vec![
+        ("id", node_id.clone()),
+        (
+            "onmouseover",
+            "this.querySelector('rect').style.fill='#333'".to_string(),
+        ),
+        (
+            "onmouseout",
+            format!("this.querySelector('rect').style.fill='{node_color}'"),
+        ),
+        ("class", "node".to_string()),
+    ];
+    if is_collapsible {
+        let child_ids = layout.children.iter().map(|c| format!("node-{}", c.id)).collect::<Vec<_>>().join(",");
+        attrs.push(("data-children", child_ids));
+        attrs.push(("onclick", "dirVizToggle(this)".to_string()));
+    }
+
+    let group = svg_builder::Group { attrs, children };
+    svg.push_str(&group.to_string());
+
+    // Draw connectors, each addressable by the child's stable id so the
+    // script can hide them alongside their target node. Colored by the
+    // child end's depth, matching drawio_xml's edge coloring rule.
     for child in &layout.children {
         let x1 = layout.x + layout.width;
         let y1 = layout.y + 16;
         let x2 = child.x;
         let y2 = child.y + 16;
-        let curve = format!(
-            "<path d='M{x1},{y1} C{x1plus},{y1} {x2minus},{y2} {x2},{y2}' stroke='{color}' stroke-width='3.5' fill='none' filter='url(#glow)' opacity='0.88' />",
-            x1 = x1,
-            y1 = y1,
-            x1plus = x1 + 30,
-            x2minus = x2 - 30,
-            x2 = x2,
-            y2 = y2,
-            color = glow_color
-        );
-        svg.push_str(&curve);
-        svg_for_layout(child, svg, &node_id);
+        let edge_color = palette.color_for(depth + 1, &child.node.node_type);
+        let curve = svg_builder::Path {
+            d: format!("M{x1},{y1} C{},{y1} {},{y2} {x2},{y2}", x1 + 30, x2 - 30),
+            attrs: vec![
+                ("id", format!("edge-{}", child.id)),
+                ("stroke", edge_color.to_string()),
+                ("stroke-width", "3.5".to_string()),
+                ("fill", "none".to_string()),
+                ("filter", "url(#glow)".to_string()),
+                ("opacity", "0.88".to_string()),
+            ],
+        };
+        svg.push_str(&curve.to_string());
+        svg_for_layout(child, svg, depth + 1, palette);
     }
 }
 
@@ -236,7 +304,7 @@ fn layout_bbox(layout: &LayoutNode, max: &mut (i32, i32)) {
 }
 
 /// Generates the SVG content with the given layout and dimensions
-fn generate_svg_content(layout: &LayoutNode, svg_width: i32, svg_height: i32) -> String {
+fn generate_svg_content(layout: &LayoutNode, svg_width: i32, svg_height: i32, palette: &Palette) -> String {
     let mut svg = format!(
         "<svg xmlns='http://www.w3.org/2000/svg' width='{svg_width}' height='{svg_height}' style='background:#14151a' font-family='monospace'>"
     );
@@ -248,7 +316,7 @@ fn generate_svg_content(layout: &LayoutNode, svg_width: i32, svg_height: i32) ->
     svg.push_str("<rect width='100%' height='100%' fill='#14151a'/>");
 
     // Generate the tree layout
-    svg_for_layout(layout, &mut svg, "root");
+    svg_for_layout(layout, &mut svg, 0, palette);
 
     // Add interactive JavaScript
     svg.push_str(include_str!("svg_script.js"));
@@ -262,30 +330,30 @@ fn generate_svg_content(layout: &LayoutNode, svg_width: i32, svg_height: i32) ->
 /// # Arguments
 ///
 /// * `root_path` - The root path to start mapping from
+/// * `palette` - Color scheme to apply to nodes and edges
 ///
 /// # Returns
 ///
 /// Returns a Result containing either the SVG string or a MapperError
-pub fn generate_svg_map(root_path: &str) -> Result<String, MapperError> {
+pub fn generate_svg_map(root_path: &str, palette: &Palette) -> Result<String, MapperError> {
     let tree = build_tree(Path::new(root_path))?;
 
-    let h_spacing = 40;
-    let v_spacing = 20;
-    let char_width = 10;
-    let padding = 18;
-    let min_width = 80;
-    let height = 32;
-
-    let layout = layout_tree(
-        &tree, 32, 32, h_spacing, v_spacing, char_width, padding, min_width, height,
-    );
+    let map_margin = 32;
+    let backend = layout::TaffyLayout::new(layout::LayoutConfig::default());
+    let mut positions = backend.position(&tree);
+    for (_, (x, y, _, _)) in positions.iter_mut() {
+        *x += map_margin;
+        *y += map_margin;
+    }
+    let ids = stable_node_ids(&tree);
+    let layout = layout_from_positions(&tree, &positions, &ids);
 
     let mut max = (0, 0);
     layout_bbox(&layout, &mut max);
     let svg_width = max.0 + 32;
     let svg_height = max.1 + 32;
 
-    Ok(generate_svg_content(&layout, svg_width, svg_height))
+    Ok(generate_svg_content(&layout, svg_width, svg_height, palette))
 }
 
 /// Saves the SVG map to a file at the specified path
@@ -306,19 +374,65 @@ mod drawio_compress;
 mod drawio_launcher;
 mod drawio_layout;
 mod drawio_xml;
+mod layout;
+mod palette;
+mod raster;
+mod render;
+mod sixel;
+mod svg_builder;
+
+use palette::Palette;
+
+/// Best-effort terminal width in columns, used to scale the `--terminal` preview.
+fn terminal_width() -> u32 {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|cols| cols.parse().ok())
+        .unwrap_or(80)
+}
+
+/// Reads `--palette {neon,rainbow,mono}` from the command line, defaulting to `neon`.
+fn palette_from_args() -> Palette {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == "--palette")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|name| Palette::from_name(name))
+        .unwrap_or(Palette::Neon)
+}
 
 fn main() -> Result<(), MapperError> {
     let root_path = ".";
     let tree = build_tree(std::path::Path::new(root_path))?;
+    let palette = palette_from_args();
+
+    if std::env::args().any(|arg| arg == "--terminal") {
+        let backend = layout::TaffyLayout::new(layout::LayoutConfig::default());
+        let positions = backend.position(&tree);
+        let ids = stable_node_ids(&tree);
+        let layout = layout_from_positions(&tree, &positions, &ids);
+        let mut max = (0, 0);
+        layout_bbox(&layout, &mut max);
+        sixel::print_sixel(&layout, max.0 + 32, max.1 + 32, terminal_width(), &palette);
+        return Ok(());
+    }
+
+    if std::env::args().any(|arg| arg == "--png") {
+        let png = render::generate_png_map(root_path, 1.0, &palette)?;
+        fs::write("repo_map.png", &png)?;
+        println!("PNG generated: repo_map.png");
+        return Ok(());
+    }
+
     // Generate and save SVG
-    let svg = generate_svg_map(root_path)?;
+    let svg = generate_svg_map(root_path, &palette)?;
     save_svg_map(&svg, "repo_map.svg")?;
     println!("SVG generated: repo_map.svg");
     // Generate and save draw.io XML
-    let file_xml = drawio_xml::generate_drawio_file_xml(&tree);
+    let file_xml = drawio_xml::generate_drawio_file_xml(&tree, &palette);
     std::fs::write("repo_map.drawio", &file_xml)?;
     println!("Draw.io XML generated: repo_map.drawio");
-    let model_xml = drawio_xml::generate_mxgraphmodel_xml(&tree);
+    let model_xml = drawio_xml::generate_mxgraphmodel_xml(&tree, &palette);
     // Compress, encode, and launch draw.io
     match drawio_compress::compress_and_encode(&model_xml) {
         Ok(encoded) => match drawio_launcher::launch_drawio_with_xml(&encoded) {
@@ -0,0 +1,47 @@
+use crate::NodeType;
+
+/// Color scheme applied to nodes and their connecting edges.
+///
+/// `Neon` reproduces the original two-tone scheme; `Rainbow` colors by tree
+/// depth instead, using `depth` passed in from whichever layout produced the
+/// node (SVG's `svg_for_layout` or draw.io's `drawio_layout`) so nested
+/// structure reads at a glance. `Mono` draws every node and edge in a single
+/// color, ignoring both depth and node type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    Neon,
+    Rainbow,
+    Mono,
+}
+
+/// An 8-hue rainbow, red through magenta, cycled by depth.
+const RAINBOW: [&str; 8] = [
+    "#ff355e", "#ff8c00", "#ffd700", "#3aff3a", "#00ced1", "#1e90ff", "#8a2be2", "#ff00ff",
+];
+
+impl Palette {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "neon" => Some(Palette::Neon),
+            "rainbow" => Some(Palette::Rainbow),
+            "mono" => Some(Palette::Mono),
+            _ => None,
+        }
+    }
+
+    /// Hex color for a node (and the edge leading to it) at `depth`.
+    ///
+    /// The existing glow filters blur whatever they're given rather than
+    /// hard-coding a color, so every palette can reuse `url(#glow)` /
+    /// `url(#textglow)` unchanged.
+    pub fn color_for(&self, depth: i32, node_type: &NodeType) -> &'static str {
+        match self {
+            Palette::Neon => match node_type {
+                NodeType::Directory => "#00fff7",
+                NodeType::File => "#39ff14",
+            },
+            Palette::Mono => "#8a8aff",
+            Palette::Rainbow => RAINBOW[depth.rem_euclid(RAINBOW.len() as i32) as usize],
+        }
+    }
+}
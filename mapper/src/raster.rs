@@ -0,0 +1,194 @@
+use crate::LayoutNode;
+use crate::palette::Palette;
+
+/// Embedded 3x5 bitmap font covering the characters that actually show up
+/// in file/directory names, so the rasterizer can label nodes without a
+/// system font dependency. Each glyph row is the low 3 bits of a `u8`
+/// (MSB-first column order); characters outside this set fall back to a
+/// small placeholder glyph rather than silently vanishing.
+mod font {
+    pub const GLYPH_WIDTH: usize = 3;
+    pub const GLYPH_HEIGHT: usize = 5;
+
+    pub fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+        match c.to_ascii_uppercase() {
+            'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+            'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+            'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+            'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+            'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+            'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+            'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+            'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+            'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+            'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+            'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+            'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+            'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+            'N' => [0b101, 0b111, 0b111, 0b111, 0b101],
+            'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+            'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+            'Q' => [0b010, 0b101, 0b101, 0b111, 0b011],
+            'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+            'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+            'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+            'U' => [0b101, 0b101, 0b101, 0b101, 0b011],
+            'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+            'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+            'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+            'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+            'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+            '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+            '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+            '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+            '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+            '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+            '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+            '6' => [0b011, 0b100, 0b110, 0b101, 0b010],
+            '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+            '8' => [0b010, 0b101, 0b010, 0b101, 0b010],
+            '9' => [0b010, 0b101, 0b011, 0b001, 0b110],
+            '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+            '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+            '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+            ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+            _ => [0b010, 0b101, 0b000, 0b101, 0b010],
+        }
+    }
+}
+
+/// Canvas background color, matching the SVG map's `#14151a` fill.
+pub const BACKGROUND: (u8, u8, u8) = (0x14, 0x15, 0x1a);
+
+pub fn hex_to_rgb(hex: &str) -> (u8, u8, u8) {
+    let hex = hex.trim_start_matches('#');
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).unwrap_or(0);
+    (channel(0..2), channel(2..4), channel(4..6))
+}
+
+/// RGB pixel buffer rasterized from a `LayoutNode` tree, shared by the
+/// `--terminal` sixel preview and the `--png` rasterizer so both stay in
+/// sync with what the SVG emitter draws.
+pub struct Canvas {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<(u8, u8, u8)>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![BACKGROUND; width * height],
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, color: (u8, u8, u8)) {
+        if x < 0 || y < 0 {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// Fills a rounded rectangle by clipping the four corner squares to a
+    /// quarter-circle of the given radius.
+    fn fill_rounded_rect(&mut self, x: i32, y: i32, w: i32, h: i32, radius: i32, color: (u8, u8, u8)) {
+        for dy in 0..h {
+            for dx in 0..w {
+                let corner_x = if dx < radius {
+                    radius - dx
+                } else if dx >= w - radius {
+                    dx - (w - radius) + 1
+                } else {
+                    0
+                };
+                let corner_y = if dy < radius {
+                    radius - dy
+                } else if dy >= h - radius {
+                    dy - (h - radius) + 1
+                } else {
+                    0
+                };
+                if corner_x > 0 && corner_y > 0 && corner_x * corner_x + corner_y * corner_y > radius * radius {
+                    continue;
+                }
+                self.set(x + dx, y + dy, color);
+            }
+        }
+    }
+
+    /// Draws `text` left-to-right starting at `(x, y)` through the embedded
+    /// `font` bitmap, one scaled pixel block per glyph dot. Scale is clamped
+    /// to at least one device pixel per glyph dot so labels stay legible
+    /// even when the overall map is shrunk well below its natural size.
+    fn draw_text(&mut self, x: i32, y: i32, text: &str, scale: f32, color: (u8, u8, u8)) {
+        let px = scale.max(1.0).round() as i32;
+        let mut cursor_x = x;
+        for ch in text.chars() {
+            let glyph = font::glyph(ch);
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..font::GLYPH_WIDTH {
+                    if bits & (1 << (font::GLYPH_WIDTH - 1 - col)) == 0 {
+                        continue;
+                    }
+                    let gx = cursor_x + col as i32 * px;
+                    let gy = y + row as i32 * px;
+                    for dy in 0..px {
+                        for dx in 0..px {
+                            self.set(gx + dx, gy + dy, color);
+                        }
+                    }
+                }
+            }
+            cursor_x += (font::GLYPH_WIDTH as i32 + 1) * px;
+        }
+    }
+
+    /// Strokes a cubic Bezier curve by walking `t` in small steps, matching
+    /// the connector curves `svg_for_layout` draws.
+    fn stroke_cubic(&mut self, p0: (f32, f32), p1: (f32, f32), p2: (f32, f32), p3: (f32, f32), color: (u8, u8, u8)) {
+        let steps = 64;
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+            let mt = 1.0 - t;
+            let x = mt * mt * mt * p0.0 + 3.0 * mt * mt * t * p1.0 + 3.0 * mt * t * t * p2.0 + t * t * t * p3.0;
+            let y = mt * mt * mt * p0.1 + 3.0 * mt * mt * t * p1.1 + 3.0 * mt * t * t * p2.1 + t * t * t * p3.1;
+            self.set(x.round() as i32, y.round() as i32, color);
+        }
+    }
+}
+
+/// Rasterizes `layout` onto `canvas` at `scale`, coloring by depth through
+/// `palette`. Each node becomes a filled rounded rect; each parent/child
+/// pair becomes a stroked connector curve, mirroring `svg_for_layout`'s glow
+/// rects and cubic paths but flattened to solid fills (no filter blur).
+pub fn draw_layout(canvas: &mut Canvas, layout: &LayoutNode, depth: i32, scale: f32, palette: &Palette) {
+    let color = hex_to_rgb(palette.color_for(depth, &layout.node.node_type));
+    let x = (layout.x as f32 * scale) as i32;
+    let y = (layout.y as f32 * scale) as i32;
+    let w = ((layout.width as f32 * scale) as i32).max(1);
+    let h = ((32.0 * scale) as i32).max(1);
+    let radius = ((12.0 * scale) as i32).max(1);
+    canvas.fill_rounded_rect(x, y, w, h, radius, color);
+
+    let px = scale.max(1.0).round() as i32;
+    let label_x = x + (14.0 * scale) as i32;
+    let label_y = y + ((h - font::GLYPH_HEIGHT as i32 * px) / 2).max(0);
+    canvas.draw_text(label_x, label_y, &layout.node.name, scale, (0, 0, 0));
+
+    for child in &layout.children {
+        let p0 = ((layout.x + layout.width) as f32 * scale, (layout.y + 16) as f32 * scale);
+        let p3 = (child.x as f32 * scale, (child.y + 16) as f32 * scale);
+        let p1 = (p0.0 + 30.0 * scale, p0.1);
+        let p2 = (p3.0 - 30.0 * scale, p3.1);
+        // Colored by the child end's depth, as `svg_for_layout` does.
+        let edge_color = hex_to_rgb(palette.color_for(depth + 1, &child.node.node_type));
+        canvas.stroke_cubic(p0, p1, p2, p3, edge_color);
+        draw_layout(canvas, child, depth + 1, scale, palette);
+    }
+}
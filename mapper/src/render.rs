@@ -0,0 +1,102 @@
+use crate::layout::TreeLayout;
+use crate::palette::Palette;
+use crate::raster::{self, Canvas};
+use crate::{MapperError, build_tree, layout, layout_bbox, layout_from_positions, stable_node_ids};
+use std::io::Write;
+use std::path::Path;
+
+/// Generates a PNG raster of the directory map without needing a browser.
+///
+/// Rasterizes the same `LayoutNode` tree the SVG/sixel paths draw into a
+/// pixmap at `scale`, then PNG-encodes it directly. The SVG's `<script>`
+/// interactivity has no raster equivalent and is dropped; its glow
+/// `<filter>` is flattened to the solid fill it blurs, so the static image
+/// matches the interactive one as closely as a raster can.
+pub fn generate_png_map(root_path: &str, scale: f32, palette: &Palette) -> Result<Vec<u8>, MapperError> {
+    let tree = build_tree(Path::new(root_path))?;
+
+    let map_margin = 32;
+    let backend = layout::TaffyLayout::new(layout::LayoutConfig::default());
+    let mut positions = backend.position(&tree);
+    for (_, (x, y, _, _)) in positions.iter_mut() {
+        *x += map_margin;
+        *y += map_margin;
+    }
+    let ids = stable_node_ids(&tree);
+    let laid_out = layout_from_positions(&tree, &positions, &ids);
+
+    let mut max = (0, 0);
+    layout_bbox(&laid_out, &mut max);
+    let width = (((max.0 + 32) as f32) * scale).ceil().max(1.0) as usize;
+    let height = (((max.1 + 32) as f32) * scale).ceil().max(1.0) as usize;
+
+    let mut canvas = Canvas::new(width, height);
+    raster::draw_layout(&mut canvas, &laid_out, 0, scale, palette);
+
+    Ok(encode_png(&canvas))
+}
+
+/// Encodes an RGB `Canvas` as a truecolor, non-interlaced PNG: signature,
+/// `IHDR`, a single `IDAT` holding zlib-compressed, unfiltered scanlines,
+/// then `IEND`.
+fn encode_png(canvas: &Canvas) -> Vec<u8> {
+    let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+    write_chunk(&mut png, b"IHDR", &ihdr_data(canvas.width as u32, canvas.height as u32));
+
+    let mut raw = Vec::with_capacity(canvas.height * (1 + canvas.width * 3));
+    for y in 0..canvas.height {
+        raw.push(0); // filter type: None
+        for x in 0..canvas.width {
+            let (r, g, b) = canvas.pixels[y * canvas.width + x];
+            raw.extend_from_slice(&[r, g, b]);
+        }
+    }
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&raw).expect("in-memory zlib encode cannot fail");
+    let compressed = encoder.finish().expect("in-memory zlib encode cannot fail");
+    write_chunk(&mut png, b"IDAT", &compressed);
+
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn ihdr_data(width: u32, height: u32) -> Vec<u8> {
+    let mut data = Vec::with_capacity(13);
+    data.extend_from_slice(&width.to_be_bytes());
+    data.extend_from_slice(&height.to_be_bytes());
+    data.push(8); // bit depth
+    data.push(2); // color type: truecolor (RGB)
+    data.push(0); // compression method
+    data.push(0); // filter method
+    data.push(0); // interlace method
+    data
+}
+
+fn write_chunk(out: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let mut crc_input = Vec::with_capacity(4 + data.len());
+    crc_input.extend_from_slice(chunk_type);
+    crc_input.extend_from_slice(data);
+    out.extend_from_slice(&crc_input);
+    out.extend_from_slice(&crc32(&crc_input).to_be_bytes());
+}
+
+/// Minimal CRC-32 (the variant PNG chunks use) so chunk checksums don't need
+/// an extra crate alongside the zlib deflate already used for draw.io export.
+fn crc32(data: &[u8]) -> u32 {
+    const POLY: u32 = 0xEDB88320;
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ POLY;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+    crc ^ 0xFFFF_FFFF
+}
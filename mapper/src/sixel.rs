@@ -0,0 +1,73 @@
+use crate::LayoutNode;
+use crate::palette::Palette;
+use crate::raster::{self, Canvas};
+
+/// Approximate pixel width of one terminal cell, used to convert the
+/// column-based `term_width` into a pixel budget. Terminals don't expose
+/// their actual cell geometry to a portable `COLUMNS`-style query, so this
+/// is a best-effort stand-in for typical monospace fonts.
+const APPROX_CELL_WIDTH_PX: f32 = 9.0;
+
+/// Rasterizes `layout` (whose extent is `map_width` x `map_height`) and
+/// prints it to stdout as a sixel image, scaled down to fit `term_width`
+/// terminal columns when the map is wider than the terminal.
+pub fn print_sixel(layout: &LayoutNode, map_width: i32, map_height: i32, term_width: u32, palette: &Palette) {
+    let target_px = term_width as f32 * APPROX_CELL_WIDTH_PX;
+    let scale = if map_width as f32 > target_px {
+        target_px / map_width as f32
+    } else {
+        1.0
+    };
+    let width = ((map_width as f32 * scale).ceil() as usize).max(1);
+    let height = ((map_height as f32 * scale).ceil() as usize).max(1);
+
+    let mut canvas = Canvas::new(width, height);
+    raster::draw_layout(&mut canvas, layout, 0, scale, palette);
+    print!("{}", encode_sixel(&canvas));
+}
+
+/// Encodes an RGB `Canvas` as a sixel DCS stream. The palette is the set of
+/// distinct colors actually painted onto the canvas (background first), each
+/// declared once with a `#n;2;r;g;b` introducer (sixel wants percentages,
+/// not 0-255). For every 6-pixel-tall band, each color emits one sixel byte
+/// per column, where the low 6 bits select which of the 6 vertical pixels in
+/// that column are set, offset by 63 (`?`). `$` returns to the start of the
+/// band between colors and `-` advances to the next band.
+fn encode_sixel(canvas: &Canvas) -> String {
+    let mut colors = vec![raster::BACKGROUND];
+    for &pixel in &canvas.pixels {
+        if !colors.contains(&pixel) {
+            colors.push(pixel);
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("\x1bPq");
+    for (i, (r, g, b)) in colors.iter().enumerate() {
+        out.push_str(&format!("#{i};2;{};{};{}", to_percent(*r), to_percent(*g), to_percent(*b)));
+    }
+
+    for band_start in (0..canvas.height).step_by(6) {
+        for (color_index, palette_color) in colors.iter().enumerate() {
+            out.push_str(&format!("#{color_index}"));
+            for x in 0..canvas.width {
+                let mut bits = 0u8;
+                for row in 0..6 {
+                    let y = band_start + row;
+                    if y < canvas.height && canvas.pixels[y * canvas.width + x] == *palette_color {
+                        bits |= 1 << row;
+                    }
+                }
+                out.push((bits + 63) as char);
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn to_percent(channel: u8) -> u32 {
+    (channel as u32 * 100 + 127) / 255
+}
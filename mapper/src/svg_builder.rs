@@ -0,0 +1,107 @@
+use std::fmt;
+
+/// Escapes text for safe inclusion as SVG/XML character data or a quoted
+/// attribute value.
+pub fn escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn render_attrs(attrs: &[(&'static str, String)]) -> String {
+    attrs
+        .iter()
+        .map(|(key, value)| format!(" {key}=\"{}\"", escape(value)))
+        .collect()
+}
+
+/// A `<rect>` element. Attribute values are XML-escaped on display.
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub rx: i32,
+    pub attrs: Vec<(&'static str, String)>,
+}
+
+impl fmt::Display for Rect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\"{}/>",
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            self.rx,
+            render_attrs(&self.attrs)
+        )
+    }
+}
+
+/// A `<path>` element, e.g. the cubic connector curves between nodes.
+pub struct Path {
+    pub d: String,
+    pub attrs: Vec<(&'static str, String)>,
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<path d=\"{}\"{} />", escape(&self.d), render_attrs(&self.attrs))
+    }
+}
+
+/// A `<text>` element whose body is XML-escaped.
+pub struct Text {
+    pub x: i32,
+    pub y: i32,
+    pub content: String,
+    pub attrs: Vec<(&'static str, String)>,
+}
+
+impl fmt::Display for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<text x=\"{}\" y=\"{}\"{}>{}</text>",
+            self.x,
+            self.y,
+            render_attrs(&self.attrs),
+            escape(&self.content)
+        )
+    }
+}
+
+/// A `<title>` element whose body is XML-escaped.
+pub struct Title(pub String);
+
+impl fmt::Display for Title {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<title>{}</title>", escape(&self.0))
+    }
+}
+
+/// A `<g>` element wrapping already-rendered children.
+pub struct Group {
+    pub attrs: Vec<(&'static str, String)>,
+    pub children: Vec<String>,
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<g{}>", render_attrs(&self.attrs))?;
+        for child in &self.children {
+            f.write_str(child)?;
+        }
+        f.write_str("</g>")
+    }
+}